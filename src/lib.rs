@@ -6,7 +6,228 @@ use pyo3::{Bound, PyRefMut};
 use pyo3_polars::PyDataFrame;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Write};
+use std::process::{Child, ChildStdin, ChildStdout, Stdio};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
 use tokio::process::Command;
+use tokio::runtime::Runtime;
+
+/// 进程级共享的多线程 tokio 运行时
+///
+/// 此前每次查询都新建一个 `Runtime`，既浪费线程又拖慢延迟。这里改为惰性初始化、
+/// 全局复用同一个运行时。
+fn shared_runtime() -> &'static Runtime {
+    static RUNTIME: OnceLock<Runtime> = OnceLock::new();
+    RUNTIME.get_or_init(|| {
+        Runtime::new().expect("初始化共享tokio运行时失败")
+    })
+}
+
+/// 每条查询后用于界定结果末尾的哨兵值
+const SESSION_SENTINEL: &str = "__RHIVE_EOQ__";
+
+/// 一个长驻的 `beeline` CLI 会话：从 stdin 读取 SQL、在 stdout 回传 CSV 结果
+///
+/// 通过复用同一个 JVM 进程，免去每条查询重新启动 beeline 的开销 (这正是
+/// `benchmark_query` 所衡量的延迟来源)。后台线程持续读取 stderr 上的 driver 日志，
+/// 解析 map/reduce 进度写入共享槽位。
+#[derive(Debug)]
+struct PooledSession {
+    jdbc_url: String,
+    child: Child,
+    stdin: ChildStdin,
+    stdout: BufReader<ChildStdout>,
+    progress: Arc<Mutex<f64>>,
+    last_used: Instant,
+}
+
+impl PooledSession {
+    /// 启动一个新的长驻 beeline 会话
+    fn spawn(jdbc_url: &str, username: &str) -> Result<Self> {
+        let mut child = std::process::Command::new("beeline")
+            .args([
+                "-u",
+                jdbc_url,
+                "-n",
+                username,
+                "--outputformat=csv2",
+                "--showHeader=false",
+                "--silent=false",
+            ])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()?;
+
+        let stdin = child
+            .stdin
+            .take()
+            .ok_or_else(|| anyhow!("无法获取beeline的stdin"))?;
+        let stdout = BufReader::new(
+            child
+                .stdout
+                .take()
+                .ok_or_else(|| anyhow!("无法获取beeline的stdout"))?,
+        );
+        let stderr = child
+            .stderr
+            .take()
+            .ok_or_else(|| anyhow!("无法获取beeline的stderr"))?;
+
+        // 后台持续解析 stderr 上的进度日志，写入共享槽位供查询读取
+        let progress = Arc::new(Mutex::new(0.0_f64));
+        let progress_writer = Arc::clone(&progress);
+        std::thread::spawn(move || {
+            for line in BufReader::new(stderr).lines().map_while(Result::ok) {
+                if let Some(percent) = parse_progress_line(&line) {
+                    if let Ok(mut slot) = progress_writer.lock() {
+                        *slot = percent;
+                    }
+                }
+            }
+        });
+
+        Ok(Self {
+            jdbc_url: jdbc_url.to_string(),
+            child,
+            stdin,
+            stdout,
+            progress,
+            last_used: Instant::now(),
+        })
+    }
+
+    /// 在会话上执行一条 SQL 并返回其 CSV 输出
+    fn run_query(&mut self, sql: &str, mut progress: Option<&mut dyn FnMut(f64)>) -> Result<String> {
+        if let Some(cb) = progress.as_deref_mut() {
+            cb(0.0);
+        }
+
+        // 追加一条哨兵查询，以其输出界定本条查询结果的末尾
+        let stmt = sql.trim().trim_end_matches(';');
+        writeln!(self.stdin, "{stmt};")?;
+        writeln!(self.stdin, "SELECT '{SESSION_SENTINEL}';")?;
+        self.stdin.flush()?;
+
+        let mut output = String::new();
+        let mut line = String::new();
+        loop {
+            line.clear();
+            let read = self.stdout.read_line(&mut line)?;
+            if read == 0 {
+                return Err(anyhow!("beeline会话意外关闭"));
+            }
+            let trimmed = line.trim_end_matches(['\n', '\r']);
+            if trimmed == SESSION_SENTINEL {
+                break;
+            }
+            output.push_str(trimmed);
+            output.push('\n');
+        }
+
+        if let Some(cb) = progress.as_deref_mut() {
+            let percent = self.progress.lock().map(|p| *p).unwrap_or(0.0);
+            if percent > 0.0 && percent < 100.0 {
+                cb(percent);
+            }
+            cb(100.0);
+        }
+
+        self.last_used = Instant::now();
+        Ok(output)
+    }
+
+    /// 会话进程是否仍存活
+    fn is_alive(&mut self) -> bool {
+        matches!(self.child.try_wait(), Ok(None))
+    }
+
+    /// 关闭会话进程
+    fn shutdown(&mut self) {
+        let _ = writeln!(self.stdin, "!quit");
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+/// 按 JDBC URL 复用预热 beeline 会话的连接池，带最大容量与空闲淘汰策略
+#[derive(Debug)]
+struct ConnectionPool {
+    idle: HashMap<String, Vec<PooledSession>>,
+    max_size: usize,
+    idle_timeout: Duration,
+}
+
+impl ConnectionPool {
+    fn new() -> Self {
+        Self {
+            idle: HashMap::new(),
+            max_size: 8,
+            idle_timeout: Duration::from_secs(300),
+        }
+    }
+
+    /// 取出一个指向给定 JDBC URL 的预热会话，池中没有存活会话时新建一个
+    fn acquire(&mut self, jdbc_url: &str, username: &str) -> Result<PooledSession> {
+        self.evict_expired();
+        if let Some(sessions) = self.idle.get_mut(jdbc_url) {
+            while let Some(mut session) = sessions.pop() {
+                if session.is_alive() {
+                    session.last_used = Instant::now();
+                    return Ok(session);
+                }
+                session.shutdown();
+            }
+        }
+        PooledSession::spawn(jdbc_url, username)
+    }
+
+    /// 将会话归还池中；已死亡或超出最大容量的直接关闭
+    fn release(&mut self, mut session: PooledSession) {
+        self.evict_expired();
+        if !session.is_alive() {
+            session.shutdown();
+            return;
+        }
+        session.last_used = Instant::now();
+        let sessions = self.idle.entry(session.jdbc_url.clone()).or_default();
+        if sessions.len() < self.max_size {
+            sessions.push(session);
+        } else {
+            session.shutdown();
+        }
+    }
+
+    /// 淘汰并关闭空闲时间超过阈值的会话
+    fn evict_expired(&mut self) {
+        let timeout = self.idle_timeout;
+        for sessions in self.idle.values_mut() {
+            sessions.retain_mut(|s| {
+                if s.last_used.elapsed() < timeout {
+                    true
+                } else {
+                    s.shutdown();
+                    false
+                }
+            });
+        }
+    }
+}
+
+/// 进程级共享连接池
+fn connection_pool() -> &'static Mutex<ConnectionPool> {
+    static POOL: OnceLock<Mutex<ConnectionPool>> = OnceLock::new();
+    POOL.get_or_init(|| Mutex::new(ConnectionPool::new()))
+}
+
+/// 根据配置构建 HiveServer2 JDBC URL
+fn jdbc_url_for(config: &HiveConfig) -> String {
+    let host = &config.host;
+    let port = config.port;
+    let database = &config.database;
+    format!("jdbc:hive2://{host}:{port}/{database}")
+}
 
 /// Hive连接配置
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -22,6 +243,22 @@ pub struct HiveConfig {
     pub database: String,
     #[pyo3(get, set)]
     pub auth: String,
+    /// 无界 SELECT 的安全行数上限；设置后会自动为其追加 LIMIT
+    #[pyo3(get, set)]
+    pub max_rows: Option<u32>,
+    /// Parquet 批量写入的对象存储暂存前缀 (如 `s3://bucket/staging`)；留空则使用本地 HDFS
+    #[pyo3(get, set)]
+    pub staging_prefix: Option<String>,
+    /// 多行 INSERT 的批大小 (每条语句的行数)；留空时默认为 100
+    #[pyo3(get, set)]
+    pub batch_size: Option<usize>,
+    /// Parquet 分片的目标字节数；文件数为数据估算大小整除该值 (至少 1 个)，
+    /// 即数据达到该值两倍及以上时才会被拆分为多个文件并发写入
+    #[pyo3(get, set)]
+    pub chunk_size: Option<usize>,
+    /// Overwrite 模式下，是否只覆盖入参涉及的分区 (动态分区覆盖)，而非整表重建
+    #[pyo3(get, set)]
+    pub dynamic_partition_overwrite: Option<bool>,
 }
 
 #[pymethods]
@@ -40,6 +277,11 @@ impl HiveConfig {
             username: username.unwrap_or_else(|| "default".to_string()),
             database: database.unwrap_or_else(|| "default".to_string()),
             auth: auth.unwrap_or_else(|| "NONE".to_string()),
+            max_rows: None,
+            staging_prefix: None,
+            batch_size: None,
+            chunk_size: None,
+            dynamic_partition_overwrite: None,
         }
     }
 
@@ -59,6 +301,8 @@ impl HiveConfig {
 pub struct RustHiveReader {
     config: HiveConfig,
     connected: bool,
+    /// 本连接持有的预热会话 (未连接时为空，查询时按需临时借用)
+    session: Mutex<Option<PooledSession>>,
 }
 
 #[pymethods]
@@ -69,6 +313,7 @@ impl RustHiveReader {
         Self {
             config,
             connected: false,
+            session: Mutex::new(None),
         }
     }
 
@@ -77,9 +322,8 @@ impl RustHiveReader {
         let host = &self.config.host;
         let port = self.config.port;
         println!("🔗 连接到Hive: {host}:{port}");
-
-        // 这里实现实际的连接逻辑
-        // 为了演示，我们模拟连接成功
+        // beeline 模式下从连接池预热一个会话，免去首条查询的 JVM 启动开销
+        self.acquire_session();
         self.connected = true;
         println!("✅ Hive连接成功 (Rust版本)");
         Ok(())
@@ -89,6 +333,7 @@ impl RustHiveReader {
     fn disconnect(&mut self) -> PyResult<()> {
         if self.connected {
             println!("🔌 断开Hive连接");
+            self.release_session();
             self.connected = false;
         }
         Ok(())
@@ -100,7 +345,18 @@ impl RustHiveReader {
     }
 
     /// 执行SQL查询并返回Polars DataFrame
-    fn query_to_polars(&self, sql: String) -> PyResult<PyDataFrame> {
+    ///
+    /// 可选的 `progress_callback` 入参是 0–100 的完成百分比。beeline 模式下，查询开始时
+    /// 以 0 调用一次；结果读取完毕后，若会话后台线程最近解析到的 map/reduce 百分比介于
+    /// 0 与 100 之间则以该值调用一次，最后以 100 调用。查询执行期间不会调用；模拟模式
+    /// (未设置 `USE_BEELINE`) 下完全不调用。
+    #[pyo3(signature = (sql, progress_callback = None))]
+    fn query_to_polars(
+        &self,
+        py: Python<'_>,
+        sql: String,
+        progress_callback: Option<PyObject>,
+    ) -> PyResult<PyDataFrame> {
         if !self.connected {
             return Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(
                 "未连接到Hive，请先调用connect()",
@@ -110,30 +366,70 @@ impl RustHiveReader {
         let preview = &sql[..std::cmp::min(sql.len(), 50)];
         println!("🔍 执行SQL查询: {preview}");
 
+        // 将 Python 回调包装为 Rust 闭包，透传最新进度百分比
+        let mut py_progress = progress_callback.map(|cb| {
+            move |percent: f64| {
+                let _ = cb.call1(py, (percent,));
+            }
+        });
+        let progress: Option<&mut dyn FnMut(f64)> =
+            py_progress.as_mut().map(|f| f as &mut dyn FnMut(f64));
+
         // 这里调用实际的查询实现
         let df = self
-            .execute_sql_query(&sql)
+            .execute_sql_query(&sql, progress)
             .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(e.to_string()))?;
 
         Ok(PyDataFrame(df))
     }
 
     /// 显示所有表
-    fn show_tables(&self) -> PyResult<PyDataFrame> {
-        self.query_to_polars("SHOW TABLES".to_string())
+    fn show_tables(&self, py: Python<'_>) -> PyResult<PyDataFrame> {
+        self.query_to_polars(py, "SHOW TABLES".to_string(), None)
     }
 
     /// 描述表结构
-    fn describe_table(&self, table_name: String) -> PyResult<PyDataFrame> {
+    fn describe_table(&self, py: Python<'_>, table_name: String) -> PyResult<PyDataFrame> {
         let sql = format!("DESCRIBE {table_name}");
-        self.query_to_polars(sql)
+        self.query_to_polars(py, sql, None)
     }
 
     /// 获取表样本数据
-    fn get_table_sample(&self, table_name: String, limit: Option<i32>) -> PyResult<PyDataFrame> {
+    fn get_table_sample(
+        &self,
+        py: Python<'_>,
+        table_name: String,
+        limit: Option<i32>,
+    ) -> PyResult<PyDataFrame> {
         let limit = limit.unwrap_or(10);
         let sql = format!("SELECT * FROM {table_name} LIMIT {limit}");
-        self.query_to_polars(sql)
+        self.query_to_polars(py, sql, None)
+    }
+
+    /// 增量读取：只拉取上次水位之后的新数据 (CDC风格)
+    ///
+    /// 按 `(database, table, column)` 记住上一次运行看到的最大水位值，下次调用时
+    /// 构建 `SELECT * FROM {table} WHERE {watermark_col} > {last_max}`（首次无水位时
+    /// 执行全量加载），走既有的 `execute_sql_query` 路径，然后扫描结果中水位列的新
+    /// 最大值并持久化到本地元数据文件。
+    #[pyo3(signature = (table_name, watermark_col, watermark_store = None))]
+    fn query_incremental(
+        &self,
+        table_name: String,
+        watermark_col: String,
+        watermark_store: Option<String>,
+    ) -> PyResult<PyDataFrame> {
+        if !self.connected {
+            return Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(
+                "未连接到Hive，请先调用connect()",
+            ));
+        }
+
+        let df = self
+            .execute_incremental_query(&table_name, &watermark_col, watermark_store.as_deref())
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(e.to_string()))?;
+
+        Ok(PyDataFrame(df))
     }
 
     /// 获取配置信息
@@ -144,45 +440,109 @@ impl RustHiveReader {
 
 impl RustHiveReader {
     /// 执行SQL查询的内部实现
-    fn execute_sql_query(&self, sql: &str) -> Result<DataFrame> {
+    fn execute_sql_query(
+        &self,
+        sql: &str,
+        progress: Option<&mut dyn FnMut(f64)>,
+    ) -> Result<DataFrame> {
+        self.execute_sql_query_inner(sql, progress, true)
+    }
+
+    /// 执行 SQL 的内部实现，`apply_cap` 控制是否对无界 SELECT 套用 `max_rows` 上限
+    ///
+    /// 增量读取路径必须关闭该上限：否则水位会按被截断结果计算，推进到从未读取的行，
+    /// 下次运行即丢数据。
+    fn execute_sql_query_inner(
+        &self,
+        sql: &str,
+        progress: Option<&mut dyn FnMut(f64)>,
+        apply_cap: bool,
+    ) -> Result<DataFrame> {
+        // 在启动任何子进程之前，先用真正的解析器做校验与分析
+        let analysis = analyze_sql(sql)?;
+        if !analysis.tables.is_empty() {
+            println!("📑 引用的表: {}", analysis.tables.join(", "));
+        }
+
+        // 无界 SELECT 且配置了安全上限时，自动追加 LIMIT (增量路径除外)
+        let effective_sql = match (analysis.kind, self.config.max_rows) {
+            (SqlKind::Select, Some(cap)) if apply_cap && !analysis.has_limit => {
+                format!("{} LIMIT {cap}", sql.trim_end().trim_end_matches(';'))
+            }
+            _ => sql.to_string(),
+        };
+
         // 方案1: 使用beeline命令行客户端
         if std::env::var("USE_BEELINE").unwrap_or_default() == "true" {
-            return self.execute_via_beeline(sql);
+            return self.execute_via_beeline(&effective_sql, progress);
         }
 
         // 方案2: 模拟数据（用于演示和测试）
-        self.execute_mock_query(sql)
+        self.execute_mock_query(&effective_sql)
     }
 
-    /// 通过beeline执行查询
-    fn execute_via_beeline(&self, sql: &str) -> Result<DataFrame> {
-        let rt = tokio::runtime::Runtime::new()?;
-        rt.block_on(async {
-            let host = &self.config.host;
-            let port = self.config.port;
-            let database = &self.config.database;
-            let jdbc_url = format!("jdbc:hive2://{host}:{port}/{database}");
+    /// 通过复用的预热 beeline 会话执行查询，并上报执行进度
+    fn execute_via_beeline(
+        &self,
+        sql: &str,
+        progress: Option<&mut dyn FnMut(f64)>,
+    ) -> Result<DataFrame> {
+        let csv_data = self.run_on_session(sql, progress)?;
+        self.parse_csv_to_dataframe(&csv_data)
+    }
 
-            let output = Command::new("beeline")
-                .args([
-                    "-u",
-                    &jdbc_url,
-                    "-e",
-                    sql,
-                    "--outputformat=csv2",
-                    "--silent=true",
-                ])
-                .output()
-                .await?;
+    /// 在连接持有的预热会话上执行 SQL；未连接时临时从池中借用一个会话
+    ///
+    /// 复用长驻的 beeline 进程，避免每条查询重付 JVM 启动成本。
+    fn run_on_session(
+        &self,
+        sql: &str,
+        progress: Option<&mut dyn FnMut(f64)>,
+    ) -> Result<String> {
+        let mut held = self.session.lock().expect("会话锁被污染");
+        if let Some(session) = held.as_mut() {
+            return session.run_query(sql, progress);
+        }
+        drop(held);
+
+        // 未持有预热会话 (未连接) 时，临时借用并在用完后归还
+        let jdbc_url = jdbc_url_for(&self.config);
+        let mut session = connection_pool()
+            .lock()
+            .expect("连接池锁被污染")
+            .acquire(&jdbc_url, &self.config.username)?;
+        let result = session.run_query(sql, progress);
+        connection_pool()
+            .lock()
+            .expect("连接池锁被污染")
+            .release(session);
+        result
+    }
 
-            if !output.status.success() {
-                let error = String::from_utf8_lossy(&output.stderr);
-                return Err(anyhow!("Beeline执行失败: {error}"));
-            }
+    /// beeline 模式下从池中取出一个预热会话并由本连接持有
+    fn acquire_session(&self) {
+        if std::env::var("USE_BEELINE").unwrap_or_default() != "true" {
+            return;
+        }
+        let jdbc_url = jdbc_url_for(&self.config);
+        match connection_pool()
+            .lock()
+            .expect("连接池锁被污染")
+            .acquire(&jdbc_url, &self.config.username)
+        {
+            Ok(session) => *self.session.lock().expect("会话锁被污染") = Some(session),
+            Err(e) => println!("⚠️  预热会话失败，将按需建立: {e}"),
+        }
+    }
 
-            let csv_data = String::from_utf8_lossy(&output.stdout);
-            self.parse_csv_to_dataframe(&csv_data)
-        })
+    /// 将本连接持有的会话归还连接池以供复用
+    fn release_session(&self) {
+        if let Some(session) = self.session.lock().expect("会话锁被污染").take() {
+            connection_pool()
+                .lock()
+                .expect("连接池锁被污染")
+                .release(session);
+        }
     }
 
     /// 模拟查询执行（用于演示）
@@ -256,6 +616,48 @@ impl RustHiveReader {
         }
     }
 
+    /// 执行增量查询的内部实现
+    fn execute_incremental_query(
+        &self,
+        table_name: &str,
+        watermark_col: &str,
+        watermark_store: Option<&str>,
+    ) -> Result<DataFrame> {
+        let store_path = self.watermark_store_path(watermark_store);
+        let key = format!("{}.{}.{}", self.config.database, table_name, watermark_col);
+
+        let mut store = load_watermark_store(&store_path)?;
+        let last_max = store.get(&key).cloned();
+
+        // 构建查询：有水位则增量，无水位则全量，按水位列排序以便稳定推进。
+        // last_max 已是按列 dtype 渲染好的合法 Hive 字面量，直接拼接即可。
+        let sql = match &last_max {
+            Some(value) => format!(
+                "SELECT * FROM {table_name} WHERE {watermark_col} > {value} ORDER BY {watermark_col}"
+            ),
+            None => format!("SELECT * FROM {table_name} ORDER BY {watermark_col}"),
+        };
+        println!("🔁 增量查询: {sql}");
+
+        // 增量路径绕过 max_rows 上限：截断结果会让水位越过未读行，导致下次丢数据
+        let df = self.execute_sql_query_inner(&sql, None, false)?;
+
+        // 扫描结果列的新最大值；空结果集保持水位不变
+        if let Some(new_max) = compute_column_max(&df, watermark_col)? {
+            store.insert(key, new_max);
+            save_watermark_store(&store_path, &store)?;
+        }
+
+        Ok(df)
+    }
+
+    /// 解析水位元数据文件路径 (默认 JSON 旁车文件)
+    fn watermark_store_path(&self, watermark_store: Option<&str>) -> String {
+        watermark_store
+            .map(|s| s.to_string())
+            .unwrap_or_else(|| "rhive_watermarks.json".to_string())
+    }
+
     /// 解析CSV数据为DataFrame
     fn parse_csv_to_dataframe(&self, csv_data: &str) -> Result<DataFrame> {
         // 这里实现CSV解析逻辑
@@ -267,6 +669,420 @@ impl RustHiveReader {
     }
 }
 
+/// 从 JSON 旁车文件加载水位记录；文件不存在时返回空表
+fn load_watermark_store(path: &str) -> Result<HashMap<String, String>> {
+    match std::fs::read_to_string(path) {
+        Ok(contents) if !contents.trim().is_empty() => {
+            serde_json::from_str(&contents).map_err(|e| anyhow!("解析水位文件失败: {e}"))
+        }
+        Ok(_) => Ok(HashMap::new()),
+        Err(ref e) if e.kind() == std::io::ErrorKind::NotFound => Ok(HashMap::new()),
+        Err(e) => Err(anyhow!("读取水位文件失败: {e}")),
+    }
+}
+
+/// 将水位记录持久化回 JSON 旁车文件
+fn save_watermark_store(path: &str, store: &HashMap<String, String>) -> Result<()> {
+    let contents =
+        serde_json::to_string_pretty(store).map_err(|e| anyhow!("序列化水位记录失败: {e}"))?;
+    std::fs::write(path, contents).map_err(|e| anyhow!("写入水位文件失败: {e}"))?;
+    Ok(())
+}
+
+/// 扫描 DataFrame 中指定列的最大值 (忽略 NULL/空值)，空结果集返回 None
+///
+/// 返回值已是按列 dtype 渲染好的合法 Hive 字面量 (数值不加引号、字符串加引号、
+/// 日期/时间戳输出 `DATE '...'` / `TIMESTAMP '...'`)，可直接拼进下次运行的 `>` 谓词，
+/// 不再依赖按字符串解析的引号猜测。
+fn compute_column_max(df: &DataFrame, column: &str) -> Result<Option<String>> {
+    if df.height() == 0 {
+        return Ok(None);
+    }
+
+    let series = df
+        .column(column)
+        .map_err(|_| anyhow!("结果集中缺少水位列: {column}"))?;
+
+    // drop_nulls 后若为空，说明没有可用于计算的水位值
+    let non_null = series.drop_nulls();
+    if non_null.is_empty() {
+        return Ok(None);
+    }
+
+    let max_value = non_null.max_reduce()?.into_value();
+    match max_value {
+        AnyValue::Null => Ok(None),
+        other => Ok(Some(format_hive_literal(&other)?)),
+    }
+}
+
+/// 解析一行 Hive driver 日志，返回该阶段的整体完成百分比 (0–100)
+///
+/// Hive 会输出类似 `Stage-1 map = 45%, reduce = 0%` 的行，这里抽取 map/reduce
+/// 两个百分比并取平均作为该阶段的整体进度；无法匹配的行返回 None。
+fn parse_progress_line(line: &str) -> Option<f64> {
+    use regex::Regex;
+    use std::sync::OnceLock;
+
+    static STAGE_RE: OnceLock<Regex> = OnceLock::new();
+    let re = STAGE_RE
+        .get_or_init(|| Regex::new(r"map\s*=\s*(\d+)%,\s*reduce\s*=\s*(\d+)%").unwrap());
+
+    let caps = re.captures(line)?;
+    let map: f64 = caps.get(1)?.as_str().parse().ok()?;
+    let reduce: f64 = caps.get(2)?.as_str().parse().ok()?;
+    Some((map + reduce) / 2.0)
+}
+
+/// 检查 HDFS 路径是否存在 (`hdfs dfs -test -e`)
+fn hdfs_exists(hdfs_path: &str) -> Result<bool> {
+    shared_runtime().block_on(async {
+        let status = Command::new("hdfs")
+            .args(["dfs", "-test", "-e", hdfs_path])
+            .status()
+            .await?;
+        Ok(status.success())
+    })
+}
+
+/// 删除 HDFS 路径 (`hdfs dfs -rm -r -f`)
+fn hdfs_delete(hdfs_path: &str) -> Result<()> {
+    shared_runtime().block_on(async {
+        let output = Command::new("hdfs")
+            .args(["dfs", "-rm", "-r", "-f", hdfs_path])
+            .output()
+            .await?;
+        if !output.status.success() {
+            let error = String::from_utf8_lossy(&output.stderr);
+            return Err(anyhow!("删除HDFS路径失败: {error}"));
+        }
+        Ok(())
+    })
+}
+
+/// 将本地文件/目录上传到 HDFS (`hdfs dfs -put`)
+fn hdfs_put(local_path: &str, hdfs_path: &str) -> Result<()> {
+    shared_runtime().block_on(async {
+        let output = Command::new("hdfs")
+            .args(["dfs", "-put", "-f", local_path, hdfs_path])
+            .output()
+            .await?;
+        if !output.status.success() {
+            let error = String::from_utf8_lossy(&output.stderr);
+            return Err(anyhow!("上传到HDFS失败: {error}"));
+        }
+        Ok(())
+    })
+}
+
+/// 在对象存储/仓库后端创建目录 (`hdfs dfs -mkdir -p`，兼容 s3a://、gs:// 等 URI)
+fn object_store_mkdir(location: &str) -> Result<()> {
+    shared_runtime().block_on(async {
+        let output = Command::new("hdfs")
+            .args(["dfs", "-mkdir", "-p", location])
+            .output()
+            .await?;
+        if !output.status.success() {
+            let error = String::from_utf8_lossy(&output.stderr);
+            return Err(anyhow!("创建对象存储目录失败: {error}"));
+        }
+        Ok(())
+    })
+}
+
+/// 删除对象存储/仓库后端路径 (`hdfs dfs -rm -r -f`，兼容 s3a://、gs:// 等 URI)
+fn object_store_delete(location: &str) -> Result<()> {
+    shared_runtime().block_on(async {
+        let output = Command::new("hdfs")
+            .args(["dfs", "-rm", "-r", "-f", location])
+            .output()
+            .await?;
+        if !output.status.success() {
+            let error = String::from_utf8_lossy(&output.stderr);
+            return Err(anyhow!("删除对象存储路径失败: {error}"));
+        }
+        Ok(())
+    })
+}
+
+/// 将本地目录下的 Parquet 文件逐个上传到对象存储前缀 (`hdfs dfs -put`，经 Hadoop FS
+/// 适配 s3a://、gs:// 等后端)。上传前确保目标目录存在；仅上传文件 (跳过子目录)。
+fn object_store_put(local_dir: &str, location: &str) -> Result<()> {
+    object_store_mkdir(location)?;
+    for entry in std::fs::read_dir(local_dir)? {
+        let path = entry?.path();
+        if path.is_file() {
+            let local_file = path.to_string_lossy();
+            object_store_put_file(&local_file, location)?;
+        }
+    }
+    Ok(())
+}
+
+/// 将单个本地文件上传到对象存储目录 (`hdfs dfs -put`)
+fn object_store_put_file(local_file: &str, location: &str) -> Result<()> {
+    object_store_mkdir(location)?;
+    shared_runtime().block_on(async {
+        let output = Command::new("hdfs")
+            .args(["dfs", "-put", "-f", local_file, location])
+            .output()
+            .await?;
+        if !output.status.success() {
+            let error = String::from_utf8_lossy(&output.stderr);
+            return Err(anyhow!("上传文件到对象存储失败: {error}"));
+        }
+        Ok(())
+    })
+}
+
+/// 将 DataFrame 写入本地 Parquet 文件
+fn write_dataframe_parquet(df: &DataFrame, path: &str) -> Result<()> {
+    let mut df_clone = df.clone();
+    let file = std::fs::File::create(path)?;
+    ParquetWriter::new(file).finish(&mut df_clone)?;
+    Ok(())
+}
+
+/// 将 DataFrame 按 `chunk_size` 拆分为多个 Parquet 文件并发写入目录
+///
+/// 参考 Polars 的启发式：`n_files = (estimated_size / chunk_size).clamp(1, huge_max)`，
+/// 据此得到每文件行数并切片，再用线程并发写出到本地目录。本函数只写本地文件；
+/// 随后的上传 (`object_store_put` / `hdfs_put`) 按顺序进行，不并发。`chunk_size` 为 None
+/// 时退化为单文件写入。
+fn write_parquet_chunks(df: &DataFrame, dir: &str, chunk_size: Option<usize>) -> Result<()> {
+    const HUGE_MAX: usize = 1024;
+
+    let height = df.height();
+    let n_files = match chunk_size {
+        Some(size) if size > 0 && height > 0 => {
+            (df.estimated_size() / size).clamp(1, HUGE_MAX)
+        }
+        _ => 1,
+    };
+
+    if n_files <= 1 {
+        return write_dataframe_parquet(df, &format!("{dir}/data.parquet"));
+    }
+
+    let rows_per_file = height.div_ceil(n_files);
+    let mut slices = Vec::with_capacity(n_files);
+    for (idx, start) in (0..height).step_by(rows_per_file).enumerate() {
+        let len = std::cmp::min(rows_per_file, height - start);
+        let slice = df.slice(start as i64, len);
+        let path = format!("{dir}/data_{idx}.parquet");
+        slices.push((path, slice));
+    }
+
+    // 并发写出各分片文件
+    std::thread::scope(|scope| -> Result<()> {
+        let handles: Vec<_> = slices
+            .iter()
+            .map(|(path, slice)| scope.spawn(move || write_dataframe_parquet(slice, path)))
+            .collect();
+        for handle in handles {
+            handle.join().map_err(|_| anyhow!("Parquet分片写入线程panic"))??;
+        }
+        Ok(())
+    })
+}
+
+/// 在目录中写入 `_SUCCESS` 标记文件，供下游检测数据就绪
+fn write_success_marker(dir: &str) -> Result<()> {
+    std::fs::write(format!("{dir}/_SUCCESS"), b"")?;
+    Ok(())
+}
+
+/// 按分区列的不同取值组合对 DataFrame 分组
+///
+/// 返回 `(目录后缀, PARTITION 子句, 去掉分区列后的数据帧)` 三元组，其中目录后缀形如
+/// `col1=val1/col2=val2`，分区列不保留在数据帧中 (其值已编码在路径里)。
+#[allow(clippy::type_complexity)]
+fn partition_groups(
+    df: &DataFrame,
+    partition_cols: &[String],
+) -> Result<Vec<(String, String, DataFrame)>> {
+    let groups = df.partition_by_stable(partition_cols.to_vec(), true)?;
+    let mut result = Vec::with_capacity(groups.len());
+
+    for group in groups {
+        let mut path_parts = Vec::with_capacity(partition_cols.len());
+        let mut spec_parts = Vec::with_capacity(partition_cols.len());
+
+        for col in partition_cols {
+            let value = group.column(col)?.get(0)?;
+            path_parts.push(format!("{col}={}", render_partition_path_value(&value)));
+            spec_parts.push(format!("{col}={}", render_partition_spec_value(&value)));
+        }
+
+        // 分区列不写入数据文件
+        let data = group.drop_many(partition_cols);
+        result.push((path_parts.join("/"), spec_parts.join(", "), data));
+    }
+
+    Ok(result)
+}
+
+/// 渲染分区值用于目录名 (不加引号)
+fn render_partition_path_value(value: &AnyValue) -> String {
+    match value {
+        AnyValue::Null => "__HIVE_DEFAULT_PARTITION__".to_string(),
+        other => format!("{other}").trim_matches('"').to_string(),
+    }
+}
+
+/// 渲染分区值用于 `PARTITION (...)` 子句 (字符串加引号)
+fn render_partition_spec_value(value: &AnyValue) -> String {
+    match value {
+        AnyValue::Null => "NULL".to_string(),
+        AnyValue::Boolean(b) => b.to_string(),
+        AnyValue::Int8(_)
+        | AnyValue::Int16(_)
+        | AnyValue::Int32(_)
+        | AnyValue::Int64(_)
+        | AnyValue::UInt8(_)
+        | AnyValue::UInt16(_)
+        | AnyValue::UInt32(_)
+        | AnyValue::UInt64(_)
+        | AnyValue::Float32(_)
+        | AnyValue::Float64(_) => format!("{value}"),
+        other => {
+            let rendered = format!("{other}");
+            let trimmed = rendered.trim_matches('"');
+            let escaped = trimmed.replace('\'', "''");
+            format!("'{escaped}'")
+        }
+    }
+}
+
+/// 解析 `DESCRIBE FORMATTED` 的输出，提取存储位置与列 (名称, 类型) 列表
+fn parse_describe_formatted(text: &str) -> Result<(String, Vec<(String, String)>)> {
+    let mut columns = Vec::new();
+    let mut location = String::new();
+    // 列定义区从开头的 `# col_name` 表头之后开始，遇到空白分隔行即结束
+    let mut in_columns = true;
+
+    for line in text.lines() {
+        let fields: Vec<&str> = line.split('\t').map(|f| f.trim()).collect();
+        let first = fields.first().copied().unwrap_or("");
+
+        if first.starts_with("Location") {
+            if let Some(value) = fields.get(1) {
+                location = value.to_string();
+            }
+        }
+
+        if in_columns {
+            // 空白分隔行：列定义区到此结束
+            if first.is_empty() {
+                in_columns = false;
+                continue;
+            }
+            // `# col_name` 表头等注释行跳过，但不结束列定义区
+            if first.starts_with('#') {
+                continue;
+            }
+            if let Some(dtype) = fields.get(1) {
+                if !dtype.is_empty() {
+                    columns.push((first.to_string(), dtype.to_string()));
+                }
+            }
+        }
+    }
+
+    if location.is_empty() {
+        return Err(anyhow!("未能从 DESCRIBE FORMATTED 输出中解析出表位置"));
+    }
+    Ok((location, columns))
+}
+
+/// SQL 语句的分类，基于 AST 而非子串匹配
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SqlKind {
+    Select,
+    Show,
+    Describe,
+    Ddl,
+    Other,
+}
+
+/// 对一条 SQL 的静态分析结果
+struct SqlAnalysis {
+    kind: SqlKind,
+    /// 顶层 SELECT 的 FROM 子句中直接列出的表名 (用于日志与增量特性)；
+    /// JOIN 的表、子查询、CTE 与集合运算中的表不收集
+    tables: Vec<String>,
+    /// SELECT 是否已带 LIMIT
+    has_limit: bool,
+}
+
+/// 使用 `sqlparser` 将 SQL 解析为 AST 并做校验/分析
+///
+/// 拒绝多语句输入，从 AST 判定语句类型，抽取顶层 FROM 子句中的表名。解析失败 (多为 Hive
+/// 专有语法) 时不报错，返回放行用的空分析结果，由 beeline 直接执行。
+fn analyze_sql(sql: &str) -> Result<SqlAnalysis> {
+    use sqlparser::ast::{SetExpr, Statement, TableFactor};
+    use sqlparser::dialect::HiveDialect;
+    use sqlparser::parser::Parser;
+
+    // Hive 专有语法 (如 `LATERAL VIEW`、`SORT BY`、`DISTRIBUTE BY`) sqlparser 未必能解析。
+    // 解析失败时不阻断查询，跳过分析与自动 LIMIT，直接放行交给 beeline 执行。
+    let statements = match Parser::parse_sql(&HiveDialect {}, sql) {
+        Ok(statements) => statements,
+        Err(e) => {
+            println!("⚠️  SQL解析跳过，交由beeline执行: {e}");
+            return Ok(SqlAnalysis {
+                kind: SqlKind::Other,
+                tables: Vec::new(),
+                has_limit: true,
+            });
+        }
+    };
+
+    if statements.is_empty() {
+        return Err(anyhow!("空SQL语句"));
+    }
+    if statements.len() > 1 {
+        return Err(anyhow!("不支持一次执行多条SQL语句"));
+    }
+
+    let stmt = &statements[0];
+    let mut tables = Vec::new();
+    let mut has_limit = false;
+
+    let kind = match stmt {
+        Statement::Query(query) => {
+            has_limit = query.limit.is_some();
+            if let SetExpr::Select(select) = query.body.as_ref() {
+                for twj in &select.from {
+                    if let TableFactor::Table { name, .. } = &twj.relation {
+                        tables.push(name.to_string());
+                    }
+                }
+            }
+            SqlKind::Select
+        }
+        Statement::ShowTables { .. }
+        | Statement::ShowColumns { .. }
+        | Statement::ShowVariables { .. } => SqlKind::Show,
+        Statement::ExplainTable { table_name, .. } => {
+            tables.push(table_name.to_string());
+            SqlKind::Describe
+        }
+        Statement::CreateTable { .. }
+        | Statement::CreateView { .. }
+        | Statement::Drop { .. }
+        | Statement::AlterTable { .. }
+        | Statement::Truncate { .. } => SqlKind::Ddl,
+        _ => SqlKind::Other,
+    };
+
+    Ok(SqlAnalysis {
+        kind,
+        tables,
+        has_limit,
+    })
+}
+
 /// 上下文管理器支持
 #[pyclass]
 pub struct RustHiveContext {
@@ -299,23 +1115,46 @@ impl RustHiveContext {
 
     // 代理方法：转发到内部的RustHiveReader
     /// 执行SQL查询并返回Polars DataFrame
-    fn query_to_polars(&self, sql: String) -> PyResult<PyDataFrame> {
-        self.reader.query_to_polars(sql)
+    #[pyo3(signature = (sql, progress_callback = None))]
+    fn query_to_polars(
+        &self,
+        py: Python<'_>,
+        sql: String,
+        progress_callback: Option<PyObject>,
+    ) -> PyResult<PyDataFrame> {
+        self.reader.query_to_polars(py, sql, progress_callback)
+    }
+
+    /// 增量读取：只拉取上次水位之后的新数据
+    #[pyo3(signature = (table_name, watermark_col, watermark_store = None))]
+    fn query_incremental(
+        &self,
+        table_name: String,
+        watermark_col: String,
+        watermark_store: Option<String>,
+    ) -> PyResult<PyDataFrame> {
+        self.reader
+            .query_incremental(table_name, watermark_col, watermark_store)
     }
 
     /// 显示所有表
-    fn show_tables(&self) -> PyResult<PyDataFrame> {
-        self.reader.show_tables()
+    fn show_tables(&self, py: Python<'_>) -> PyResult<PyDataFrame> {
+        self.reader.show_tables(py)
     }
 
     /// 描述表结构
-    fn describe_table(&self, table_name: String) -> PyResult<PyDataFrame> {
-        self.reader.describe_table(table_name)
+    fn describe_table(&self, py: Python<'_>, table_name: String) -> PyResult<PyDataFrame> {
+        self.reader.describe_table(py, table_name)
     }
 
     /// 获取表样本数据
-    fn get_table_sample(&self, table_name: String, limit: Option<i32>) -> PyResult<PyDataFrame> {
-        self.reader.get_table_sample(table_name, limit)
+    fn get_table_sample(
+        &self,
+        py: Python<'_>,
+        table_name: String,
+        limit: Option<i32>,
+    ) -> PyResult<PyDataFrame> {
+        self.reader.get_table_sample(py, table_name, limit)
     }
 
     /// 检查连接状态
@@ -365,7 +1204,14 @@ fn config_from_dict(config_dict: &Bound<'_, PyDict>) -> PyResult<HiveConfig> {
         .map(|v| v.extract::<String>())
         .transpose()?;
 
-    Ok(HiveConfig::new(host, port, username, database, auth))
+    let max_rows = config_dict
+        .get_item("max_rows")?
+        .map(|v| v.extract::<u32>())
+        .transpose()?;
+
+    let mut config = HiveConfig::new(host, port, username, database, auth);
+    config.max_rows = max_rows;
+    Ok(config)
 }
 
 /// 便捷函数：创建默认配置
@@ -405,8 +1251,13 @@ fn benchmark_query(
 
     let start = std::time::Instant::now();
 
+    // 记录最近一次查询的最终进度百分比，便于在结果中体现执行完成度
+    let mut last_progress = 0.0_f64;
     for _ in 0..iterations {
-        let _result = reader.query_to_polars(sql.clone())?;
+        let mut track = |percent: f64| last_progress = percent;
+        let _result = reader
+            .execute_sql_query(&sql, Some(&mut track))
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(e.to_string()))?;
     }
 
     let duration = start.elapsed();
@@ -422,6 +1273,7 @@ fn benchmark_query(
         "queries_per_second".to_string(),
         iterations as f64 / duration.as_secs_f64(),
     );
+    results.insert("final_progress".to_string(), last_progress);
 
     Ok(results)
 }
@@ -490,6 +1342,8 @@ impl WriteMode {
 pub struct RustHiveWriter {
     config: HiveConfig,
     connected: bool,
+    /// 本连接持有的预热会话 (未连接时为空，DDL 执行时按需临时借用)
+    session: Mutex<Option<PooledSession>>,
 }
 
 #[pymethods]
@@ -500,6 +1354,7 @@ impl RustHiveWriter {
         Self {
             config,
             connected: false,
+            session: Mutex::new(None),
         }
     }
 
@@ -508,8 +1363,7 @@ impl RustHiveWriter {
         let host = &self.config.host;
         let port = self.config.port;
         println!("🔗 连接到Hive写入器: {host}:{port}");
-
-        // 这里实现实际的连接逻辑
+        self.acquire_session();
         self.connected = true;
         println!("✅ Hive写入器连接成功 (Rust版本)");
         Ok(())
@@ -519,6 +1373,7 @@ impl RustHiveWriter {
     fn disconnect(&mut self) -> PyResult<()> {
         if self.connected {
             println!("🔌 断开Hive写入器连接");
+            self.release_session();
             self.connected = false;
         }
         Ok(())
@@ -577,6 +1432,50 @@ impl RustHiveWriter {
         Ok(())
     }
 
+    /// 将查询结果物化为 Hive 表 (CTAS)
+    ///
+    /// 按既有的 `WriteMode` 语义执行 `DROP TABLE IF EXISTS ... ; CREATE TABLE ...
+    /// STORED AS {stored_as} AS {sql}` 序列；`Append` 模式在表已存在时改走
+    /// `INSERT INTO ... {sql}`。
+    #[pyo3(signature = (table_name, sql, mode = None, stored_as = None))]
+    fn create_table_as_select(
+        &self,
+        table_name: String,
+        sql: String,
+        mode: Option<WriteMode>,
+        stored_as: Option<String>,
+    ) -> PyResult<()> {
+        if !self.connected {
+            return Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(
+                "未连接到Hive，请先调用connect()",
+            ));
+        }
+
+        let mode = mode.unwrap_or(WriteMode::ErrorIfExists);
+        let stored_as = stored_as.unwrap_or_else(|| "PARQUET".to_string());
+
+        self.execute_ctas(&table_name, &sql, &mode, &stored_as)
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(e.to_string()))?;
+
+        println!("✅ CTAS 完成: {table_name}");
+        Ok(())
+    }
+
+    /// 查询表的存储位置与列信息
+    ///
+    /// 通过 `DESCRIBE FORMATTED {table}` 解析底层 HDFS/S3 路径与列列表，便于下游
+    /// 直接用 Polars 的 Parquet 读取器消费产出的文件。
+    fn get_table_location(&self, table_name: String) -> PyResult<(String, Vec<(String, String)>)> {
+        if !self.connected {
+            return Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(
+                "未连接到Hive，请先调用connect()",
+            ));
+        }
+
+        self.describe_formatted(&table_name)
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(e.to_string()))
+    }
+
     /// 删除表
     fn drop_table(&self, table_name: String, if_exists: Option<bool>) -> PyResult<()> {
         if !self.connected {
@@ -606,6 +1505,53 @@ impl RustHiveWriter {
 }
 
 impl RustHiveWriter {
+    /// 在连接持有的预热会话上执行 SQL；未连接时临时从池中借用一个会话
+    fn run_on_session(&self, sql: &str) -> Result<String> {
+        let mut held = self.session.lock().expect("会话锁被污染");
+        if let Some(session) = held.as_mut() {
+            return session.run_query(sql, None);
+        }
+        drop(held);
+
+        let jdbc_url = jdbc_url_for(&self.config);
+        let mut session = connection_pool()
+            .lock()
+            .expect("连接池锁被污染")
+            .acquire(&jdbc_url, &self.config.username)?;
+        let result = session.run_query(sql, None);
+        connection_pool()
+            .lock()
+            .expect("连接池锁被污染")
+            .release(session);
+        result
+    }
+
+    /// beeline 模式下从池中取出一个预热会话并由本连接持有
+    fn acquire_session(&self) {
+        if std::env::var("USE_BEELINE").unwrap_or_default() != "true" {
+            return;
+        }
+        let jdbc_url = jdbc_url_for(&self.config);
+        match connection_pool()
+            .lock()
+            .expect("连接池锁被污染")
+            .acquire(&jdbc_url, &self.config.username)
+        {
+            Ok(session) => *self.session.lock().expect("会话锁被污染") = Some(session),
+            Err(e) => println!("⚠️  预热会话失败，将按需建立: {e}"),
+        }
+    }
+
+    /// 将本连接持有的会话归还连接池以供复用
+    fn release_session(&self) {
+        if let Some(session) = self.session.lock().expect("会话锁被污染").take() {
+            connection_pool()
+                .lock()
+                .expect("连接池锁被污染")
+                .release(session);
+        }
+    }
+
     /// 执行写入操作的内部实现
     fn execute_write_operation(
         &self,
@@ -618,6 +1564,11 @@ impl RustHiveWriter {
         // 检查表是否存在
         let table_exists = self.check_table_exists(table_name)?;
 
+        // 分区表在 Overwrite 模式下是否走动态分区覆盖 (只替换入参涉及的分区)
+        let dynamic_overwrite = matches!(mode, WriteMode::Overwrite)
+            && self.config.dynamic_partition_overwrite.unwrap_or(false)
+            && matches!(partition_cols, Some(cols) if !cols.is_empty());
+
         match mode {
             WriteMode::ErrorIfExists if table_exists => {
                 return Err(anyhow!("表 {table_name} 已存在"));
@@ -626,45 +1577,86 @@ impl RustHiveWriter {
                 println!("⚠️  表 {table_name} 已存在，忽略写入");
                 return Ok(());
             }
-            WriteMode::Overwrite if table_exists => {
+            // 静态整表覆盖：删除后重建；动态分区覆盖则保留整表，仅覆盖涉及分区
+            WriteMode::Overwrite if table_exists && !dynamic_overwrite => {
                 println!("🔄 覆盖模式：删除现有表 {table_name}");
                 self.execute_ddl(&format!("DROP TABLE {table_name}"))?;
             }
             _ => {}
         }
 
-        // 如果表不存在且需要创建表，则创建表结构
-        if (!table_exists || matches!(mode, WriteMode::Overwrite)) && create_table {
+        // 表不存在时始终建表 (动态分区覆盖的首次运行同样需要)；表已存在时，
+        // 仅静态整表覆盖才重建，动态分区覆盖保留既有表结构。
+        let need_create = create_table
+            && (!table_exists || (matches!(mode, WriteMode::Overwrite) && !dynamic_overwrite));
+        if need_create {
             self.create_table_schema(df, table_name, partition_cols)?;
         }
 
-        // 写入数据
-        self.insert_dataframe_data(df, table_name, partition_cols)
+        // 写入数据 (覆盖模式下以 OVERWRITE 语义装载)
+        let overwrite = matches!(mode, WriteMode::Overwrite);
+        self.insert_dataframe_data(df, table_name, partition_cols, overwrite)
     }
 
-    /// 检查表是否存在
-    fn check_table_exists(&self, table_name: &str) -> Result<bool> {
-        // 方案1: 使用beeline命令检查
-        if std::env::var("USE_BEELINE").unwrap_or_default() == "true" {
-            return self.check_table_exists_via_beeline(table_name);
+    /// 执行 CTAS 序列 (按写入模式分派)
+    fn execute_ctas(
+        &self,
+        table_name: &str,
+        sql: &str,
+        mode: &WriteMode,
+        stored_as: &str,
+    ) -> Result<()> {
+        let table_exists = self.check_table_exists(table_name)?;
+
+        match mode {
+            WriteMode::ErrorIfExists if table_exists => {
+                return Err(anyhow!("表 {table_name} 已存在"));
+            }
+            WriteMode::Ignore if table_exists => {
+                println!("⚠️  表 {table_name} 已存在，忽略 CTAS");
+                return Ok(());
+            }
+            // 追加模式：表已存在则直接插入查询结果
+            WriteMode::Append if table_exists => {
+                let insert_sql = format!("INSERT INTO TABLE {table_name} {sql}");
+                return self.execute_ddl(&insert_sql);
+            }
+            WriteMode::Overwrite if table_exists => {
+                self.execute_ddl(&format!("DROP TABLE IF EXISTS {table_name}"))?;
+            }
+            _ => {}
         }
 
-        // 方案2: 模拟检查（用于演示）
-        println!("🔍 检查表是否存在: {table_name}");
-        // 这里可以模拟表存在性检查逻辑
-        Ok(false) // 默认假设表不存在
+        let create_sql =
+            format!("CREATE TABLE {table_name} STORED AS {stored_as} AS {sql}");
+        self.execute_ddl(&create_sql)
     }
 
-    /// 通过beeline检查表是否存在
-    fn check_table_exists_via_beeline(&self, table_name: &str) -> Result<bool> {
-        let rt = tokio::runtime::Runtime::new()?;
-        rt.block_on(async {
-            let host = &self.config.host;
-            let port = self.config.port;
-            let database = &self.config.database;
-            let jdbc_url = format!("jdbc:hive2://{host}:{port}/{database}");
+    /// 通过 `DESCRIBE FORMATTED` 解析表的存储位置与列信息
+    fn describe_formatted(&self, table_name: &str) -> Result<(String, Vec<(String, String)>)> {
+        // 方案1: 使用beeline查询元数据
+        if std::env::var("USE_BEELINE").unwrap_or_default() == "true" {
+            return self.describe_formatted_via_beeline(table_name);
+        }
 
-            let sql = format!("SHOW TABLES LIKE '{table_name}'");
+        // 方案2: 模拟返回 (用于演示)
+        println!("🔍 解析表位置: {table_name}");
+        let location = format!("hdfs:///user/hive/warehouse/{table_name}");
+        let columns = vec![
+            ("id".to_string(), "bigint".to_string()),
+            ("name".to_string(), "string".to_string()),
+        ];
+        Ok((location, columns))
+    }
+
+    /// 通过beeline执行 `DESCRIBE FORMATTED` 并解析输出
+    fn describe_formatted_via_beeline(
+        &self,
+        table_name: &str,
+    ) -> Result<(String, Vec<(String, String)>)> {
+        shared_runtime().block_on(async {
+            let jdbc_url = jdbc_url_for(&self.config);
+            let sql = format!("DESCRIBE FORMATTED {table_name}");
 
             let output = Command::new("beeline")
                 .args([
@@ -672,21 +1664,42 @@ impl RustHiveWriter {
                     &jdbc_url,
                     "-e",
                     &sql,
-                    "--outputformat=csv2",
+                    "--outputformat=tsv2",
                     "--silent=true",
                 ])
                 .output()
                 .await?;
 
             if !output.status.success() {
-                return Err(anyhow!("检查表存在性失败"));
+                let error = String::from_utf8_lossy(&output.stderr);
+                return Err(anyhow!("DESCRIBE FORMATTED 失败: {error}"));
             }
 
-            let result = String::from_utf8_lossy(&output.stdout);
-            Ok(!result.trim().is_empty())
+            let text = String::from_utf8_lossy(&output.stdout);
+            parse_describe_formatted(&text)
         })
     }
 
+    /// 检查表是否存在
+    fn check_table_exists(&self, table_name: &str) -> Result<bool> {
+        // 方案1: 使用beeline命令检查
+        if std::env::var("USE_BEELINE").unwrap_or_default() == "true" {
+            return self.check_table_exists_via_beeline(table_name);
+        }
+
+        // 方案2: 模拟检查（用于演示）
+        println!("🔍 检查表是否存在: {table_name}");
+        // 这里可以模拟表存在性检查逻辑
+        Ok(false) // 默认假设表不存在
+    }
+
+    /// 通过复用的预热 beeline 会话检查表是否存在
+    fn check_table_exists_via_beeline(&self, table_name: &str) -> Result<bool> {
+        let sql = format!("SHOW TABLES LIKE '{table_name}'");
+        let result = self.run_on_session(&sql)?;
+        Ok(!result.trim().is_empty())
+    }
+
     /// 根据DataFrame创建表结构
     fn create_table_schema(
         &self,
@@ -734,22 +1747,42 @@ impl RustHiveWriter {
         self.execute_ddl(&create_sql)
     }
 
-    /// 将Polars数据类型转换为Hive数据类型
+    /// 将Polars数据类型转换为Hive数据类型 (支持嵌套类型递归映射)
     fn polars_to_hive_type(&self, dtype: &DataType) -> Result<String> {
         let hive_type = match dtype {
-            DataType::Boolean => "BOOLEAN",
-            DataType::Int8 | DataType::Int16 | DataType::Int32 => "INT",
-            DataType::Int64 => "BIGINT",
-            DataType::UInt8 | DataType::UInt16 | DataType::UInt32 => "INT",
-            DataType::UInt64 => "BIGINT",
-            DataType::Float32 => "FLOAT",
-            DataType::Float64 => "DOUBLE",
-            DataType::String => "STRING",
-            DataType::Date => "DATE",
-            DataType::Datetime(_, _) => "TIMESTAMP",
+            DataType::Boolean => "BOOLEAN".to_string(),
+            DataType::Int8 | DataType::Int16 | DataType::Int32 => "INT".to_string(),
+            DataType::Int64 => "BIGINT".to_string(),
+            DataType::UInt8 | DataType::UInt16 | DataType::UInt32 => "INT".to_string(),
+            DataType::UInt64 => "BIGINT".to_string(),
+            DataType::Float32 => "FLOAT".to_string(),
+            DataType::Float64 => "DOUBLE".to_string(),
+            DataType::String => "STRING".to_string(),
+            DataType::Date => "DATE".to_string(),
+            DataType::Datetime(_, _) => "TIMESTAMP".to_string(),
+            DataType::Decimal(precision, scale) => {
+                // Hive DECIMAL 默认 (10,0)，缺省时沿用该约定
+                let p = precision.unwrap_or(10);
+                let s = scale.unwrap_or(0);
+                format!("DECIMAL({p},{s})")
+            }
+            DataType::List(inner) => {
+                let inner_type = self.polars_to_hive_type(inner)?;
+                format!("ARRAY<{inner_type}>")
+            }
+            DataType::Struct(fields) => {
+                let field_defs = fields
+                    .iter()
+                    .map(|f| {
+                        let field_type = self.polars_to_hive_type(f.dtype())?;
+                        Ok(format!("{}:{field_type}", f.name()))
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+                format!("STRUCT<{}>", field_defs.join(","))
+            }
             _ => return Err(anyhow!("不支持的数据类型: {:?}", dtype)),
         };
-        Ok(hive_type.to_string())
+        Ok(hive_type)
     }
 
     /// 执行DDL语句
@@ -764,27 +1797,10 @@ impl RustHiveWriter {
         Ok(())
     }
 
-    /// 通过beeline执行DDL
+    /// 通过复用的预热 beeline 会话执行DDL
     fn execute_ddl_via_beeline(&self, sql: &str) -> Result<()> {
-        let rt = tokio::runtime::Runtime::new()?;
-        rt.block_on(async {
-            let host = &self.config.host;
-            let port = self.config.port;
-            let database = &self.config.database;
-            let jdbc_url = format!("jdbc:hive2://{host}:{port}/{database}");
-
-            let output = Command::new("beeline")
-                .args(["-u", &jdbc_url, "-e", sql, "--silent=true"])
-                .output()
-                .await?;
-
-            if !output.status.success() {
-                let error = String::from_utf8_lossy(&output.stderr);
-                return Err(anyhow!("DDL执行失败: {error}"));
-            }
-
-            Ok(())
-        })
+        self.run_on_session(sql)?;
+        Ok(())
     }
 
     /// 插入DataFrame数据
@@ -793,6 +1809,7 @@ impl RustHiveWriter {
         df: &DataFrame,
         table_name: &str,
         partition_cols: &Option<Vec<String>>,
+        overwrite: bool,
     ) -> Result<()> {
         // 方案1: 通过CSV文件和LOAD DATA方式
         if std::env::var("USE_CSV_LOAD").unwrap_or_default() == "true" {
@@ -801,7 +1818,12 @@ impl RustHiveWriter {
 
         // 方案2: 通过Parquet文件和外部表方式
         if std::env::var("USE_PARQUET_LOAD").unwrap_or_default() == "true" {
-            return self.insert_via_parquet_load(df, table_name, partition_cols);
+            return self.insert_via_parquet_load(df, table_name, partition_cols, overwrite);
+        }
+
+        // 分区表统一走分区目录写入路径 (INSERT 语句无法表达目录分区布局)
+        if matches!(partition_cols, Some(cols) if !cols.is_empty()) {
+            return self.insert_via_parquet_load(df, table_name, partition_cols, overwrite);
         }
 
         // 方案3: 生成INSERT语句（适合小数据量）
@@ -836,29 +1858,155 @@ impl RustHiveWriter {
         Ok(())
     }
 
-    /// 通过Parquet文件插入数据 (简化版本)
+    /// 通过Parquet文件批量写入数据 (列式高吞吐路径)
+    ///
+    /// 将 DataFrame 写为一个或多个 Parquet 文件，上传到仓库的后端存储 (本地 HDFS
+    /// 或 `HiveConfig.staging_prefix` 指定的对象存储前缀)，再通过 `LOAD DATA INPATH`
+    /// 装入托管表，或创建 `EXTERNAL TABLE ... LOCATION` 指向暂存前缀。`partition_cols`
+    /// 会按分区键组合拆分文件并生成对应的目录布局与 `PARTITION (...)` 子句。加载完成
+    /// 后写入 `_SUCCESS` 标记；仅在配置了 `staging_prefix` 时该标记会上传到表前缀供下游
+    /// 检测就绪，本地 HDFS 模式下标记只写在本地暂存目录中，随该目录一起删除。
     fn insert_via_parquet_load(
         &self,
-        _df: &DataFrame,
+        df: &DataFrame,
         table_name: &str,
-        _partition_cols: &Option<Vec<String>>,
+        partition_cols: &Option<Vec<String>>,
+        overwrite: bool,
     ) -> Result<()> {
-        // 创建临时文件路径
-        let temp_file = format!(
-            "/tmp/{table_name}_{}.parquet",
-            chrono::Utc::now().timestamp()
-        );
+        let timestamp = chrono::Utc::now().timestamp();
+        let staging_dir = format!("/tmp/{table_name}_{timestamp}");
+        std::fs::create_dir_all(&staging_dir)?;
+
+        // 无论后续步骤成功与否，离开本函数时都清理本地暂存目录，避免 /tmp 泄漏
+        struct StagingCleanup<'a>(&'a str);
+        impl Drop for StagingCleanup<'_> {
+            fn drop(&mut self) {
+                let _ = std::fs::remove_dir_all(self.0);
+            }
+        }
+        let _cleanup = StagingCleanup(&staging_dir);
+
+        match partition_cols {
+            Some(cols) if !cols.is_empty() => {
+                self.stage_partitioned_parquet(df, table_name, &staging_dir, cols, overwrite)?;
+                // 让 Hive 感知新写入的分区目录
+                self.execute_ddl(&format!("MSCK REPAIR TABLE {table_name}"))?;
+            }
+            _ => {
+                write_parquet_chunks(df, &staging_dir, self.config.chunk_size)?;
+                self.register_parquet(table_name, &staging_dir, None, overwrite)?;
+            }
+        }
 
-        println!("📦 将生成Parquet文件: {temp_file}");
-        println!("📋 请使用外部工具将DataFrame保存为Parquet并上传到HDFS");
-        println!("💡 提示: 可以使用 df.write_parquet() 方法保存文件");
+        // 写入 _SUCCESS 标记表示本次加载完整结束 (对象存储场景下一并上传到表前缀)
+        write_success_marker(&staging_dir)?;
+        if let Some(prefix) = &self.config.staging_prefix {
+            let base = format!("{}/{table_name}", prefix.trim_end_matches('/'));
+            object_store_put_file(&format!("{staging_dir}/_SUCCESS"), &base)?;
+        }
 
-        // 这里可以添加自动上传到HDFS的逻辑
-        // 由于ParquetWriter的API问题，暂时使用提示信息
+        Ok(())
+    }
 
+    /// 按分区键组合拆分并暂存 Parquet 文件 (分区列不写入数据文件)
+    fn stage_partitioned_parquet(
+        &self,
+        df: &DataFrame,
+        table_name: &str,
+        staging_dir: &str,
+        partition_cols: &[String],
+        overwrite: bool,
+    ) -> Result<()> {
+        let groups = partition_groups(df, partition_cols)?;
+        for (path_suffix, spec, part_df) in groups {
+            let dir = format!("{staging_dir}/{path_suffix}");
+            std::fs::create_dir_all(&dir)?;
+            // 大分区按 chunk_size 拆分为多个文件并发写入
+            write_parquet_chunks(&part_df, &dir, self.config.chunk_size)?;
+            // 动态分区覆盖：仅对入参涉及的分区使用 OVERWRITE，未涉及分区保持不变
+            self.register_parquet(table_name, &dir, Some((&path_suffix, &spec)), overwrite)?;
+        }
         Ok(())
     }
 
+    /// 将暂存的 Parquet 数据注册进 Hive (LOAD DATA 或 外部表)
+    ///
+    /// `partition` 为分区表时携带 `(key=val/... 目录后缀, PARTITION 子句)`，无分区时为 None。
+    fn register_parquet(
+        &self,
+        table_name: &str,
+        local_dir: &str,
+        partition: Option<(&str, &str)>,
+        overwrite: bool,
+    ) -> Result<()> {
+        let partition_spec = partition.map(|(_, spec)| spec);
+        match &self.config.staging_prefix {
+            // 配置了对象存储前缀：上传 Parquet 到前缀，再注册外部表/分区
+            Some(prefix) => {
+                let base = format!("{}/{table_name}", prefix.trim_end_matches('/'));
+                match partition {
+                    // 分区表：上传到 key=val 目录，并为该分区单独注册 LOCATION
+                    Some((path_suffix, spec)) => {
+                        let location = format!("{base}/{path_suffix}");
+                        if overwrite {
+                            object_store_delete(&location)?;
+                        }
+                        println!("☁️  上传分区到对象存储: {location}");
+                        object_store_put(local_dir, &location)?;
+                        // 确保外部表存在 (分区列由建表阶段的 PARTITIONED BY 定义)
+                        let create_sql = format!(
+                            "CREATE EXTERNAL TABLE IF NOT EXISTS {table_name} STORED AS PARQUET LOCATION '{base}'"
+                        );
+                        self.execute_ddl(&create_sql)?;
+                        let add_sql = format!(
+                            "ALTER TABLE {table_name} ADD IF NOT EXISTS PARTITION ({spec}) LOCATION '{location}'"
+                        );
+                        self.execute_ddl(&add_sql)
+                    }
+                    // 非分区表：上传到表前缀并注册外部表
+                    None => {
+                        if overwrite {
+                            object_store_delete(&base)?;
+                        }
+                        println!("☁️  上传数据到对象存储: {base}");
+                        object_store_put(local_dir, &base)?;
+                        let create_sql = format!(
+                            "CREATE EXTERNAL TABLE IF NOT EXISTS {table_name} STORED AS PARQUET LOCATION '{base}'"
+                        );
+                        self.execute_ddl(&create_sql)
+                    }
+                }
+            }
+            // 本地 HDFS：先将本地 Parquet 暂存到 HDFS，再 LOAD DATA INPATH 装入托管表
+            None => {
+                let partition_clause = match partition_spec {
+                    Some(spec) => format!(" PARTITION ({spec})"),
+                    None => String::new(),
+                };
+
+                // HDFS 暂存路径：存在则先删除陈旧文件，再上传，加载后清理
+                let hdfs_path = format!(
+                    "/tmp/rhive_staging/{}",
+                    local_dir.trim_start_matches('/').replace('/', "_")
+                );
+                if hdfs_exists(&hdfs_path)? {
+                    hdfs_delete(&hdfs_path)?;
+                }
+                hdfs_put(local_dir, &hdfs_path)?;
+
+                let overwrite_kw = if overwrite { "OVERWRITE " } else { "" };
+                let load_sql = format!(
+                    "LOAD DATA INPATH '{hdfs_path}' {overwrite_kw}INTO TABLE {table_name}{partition_clause}"
+                );
+                let result = self.execute_ddl(&load_sql);
+
+                // 无论加载成功与否都尝试清理 HDFS 暂存文件
+                let _ = hdfs_delete(&hdfs_path);
+                result
+            }
+        }
+    }
+
     /// 通过INSERT语句插入数据（适合小数据量）
     fn insert_via_sql_statements(
         &self,
@@ -879,16 +2027,18 @@ impl RustHiveWriter {
             .collect();
         let column_list = columns.join(", ");
 
-        // 批量插入数据
-        let batch_size = 100;
-        for chunk_start in (0..rows_count).step_by(batch_size) {
+        // 批量插入数据：每条语句生成多行 VALUES，批大小可配置
+        let batch_size = self.config.batch_size.unwrap_or(100).max(1);
+        for (batch_idx, chunk_start) in (0..rows_count).step_by(batch_size).enumerate() {
             let chunk_end = std::cmp::min(chunk_start + batch_size, rows_count);
             let chunk_df = df.slice(chunk_start as i64, chunk_end - chunk_start);
 
             let values = self.dataframe_to_values_string(&chunk_df)?;
             let insert_sql = format!("INSERT INTO {table_name} ({column_list}) VALUES {values}");
 
-            self.execute_ddl(&insert_sql)?;
+            // 批次失败时，报告出错的批次序号以便定位
+            self.execute_ddl(&insert_sql)
+                .map_err(|e| anyhow!("第 {batch_idx} 批 (行 {chunk_start}..{chunk_end}) 写入失败: {e}"))?;
         }
 
         Ok(())
@@ -912,35 +2062,142 @@ impl RustHiveWriter {
         Ok(values.join(", "))
     }
 
-    /// 格式化列值
+    /// 格式化列值为合法的 Hive 字面量
     fn format_column_value(&self, column: &Series, row_idx: usize) -> Result<String> {
         let value = column.get(row_idx)?;
-        let formatted = match value {
-            AnyValue::Null => "NULL".to_string(),
-            AnyValue::Boolean(b) => b.to_string(),
-            AnyValue::Int8(i) => i.to_string(),
-            AnyValue::Int16(i) => i.to_string(),
-            AnyValue::Int32(i) => i.to_string(),
-            AnyValue::Int64(i) => i.to_string(),
-            AnyValue::UInt8(i) => i.to_string(),
-            AnyValue::UInt16(i) => i.to_string(),
-            AnyValue::UInt32(i) => i.to_string(),
-            AnyValue::UInt64(i) => i.to_string(),
-            AnyValue::Float32(f) => f.to_string(),
-            AnyValue::Float64(f) => f.to_string(),
-            _ => {
-                // 处理字符串和其他类型，统一转换为字符串
-                let str_value = format!("{value}");
-                if str_value.contains('"') || str_value.contains('\'') {
-                    let escaped_value = str_value.replace('\'', "''");
-                    format!("'{escaped_value}'")
-                } else {
-                    format!("'{str_value}'")
-                }
+        format_hive_literal(&value)
+    }
+}
+
+/// 将单个 `AnyValue` 渲染为合法的 Hive SQL 字面量
+///
+/// 数值/布尔/NULL 直接渲染；日期/时间戳输出 `DATE '...'` / `TIMESTAMP '...'`；
+/// Decimal 按标度无浮点误差地渲染；二进制输出 `unhex('...')`；List/Struct 分别生成
+/// `array(...)` / `named_struct(...)` 构造器；字符串做转义后加单引号。
+fn format_hive_literal(value: &AnyValue) -> Result<String> {
+    let formatted = match value {
+        AnyValue::Null => "NULL".to_string(),
+        AnyValue::Boolean(b) => b.to_string(),
+        AnyValue::Int8(i) => i.to_string(),
+        AnyValue::Int16(i) => i.to_string(),
+        AnyValue::Int32(i) => i.to_string(),
+        AnyValue::Int64(i) => i.to_string(),
+        AnyValue::UInt8(i) => i.to_string(),
+        AnyValue::UInt16(i) => i.to_string(),
+        AnyValue::UInt32(i) => i.to_string(),
+        AnyValue::UInt64(i) => i.to_string(),
+        AnyValue::Float32(f) => f.to_string(),
+        AnyValue::Float64(f) => f.to_string(),
+        AnyValue::Date(days) => {
+            let date = chrono::DateTime::from_timestamp(*days as i64 * 86_400, 0)
+                .ok_or_else(|| anyhow!("非法的日期值: {days}"))?
+                .date_naive();
+            format!("DATE '{}'", date.format("%Y-%m-%d"))
+        }
+        AnyValue::Datetime(v, unit, _) | AnyValue::DatetimeOwned(v, unit, _) => {
+            let ts = render_timestamp(*v, *unit)?;
+            format!("TIMESTAMP '{ts}'")
+        }
+        AnyValue::Decimal(v, scale) => render_decimal(*v, *scale),
+        AnyValue::Binary(bytes) => format!("unhex('{}')", to_hex(bytes)),
+        AnyValue::BinaryOwned(bytes) => format!("unhex('{}')", to_hex(bytes)),
+        AnyValue::List(series) => {
+            let items = series
+                .iter()
+                .map(|av| format_hive_literal(&av))
+                .collect::<Result<Vec<_>>>()?;
+            format!("array({})", items.join(", "))
+        }
+        // 借用型 Struct 先转为 owned，再走与 StructOwned 相同的公共访问路径，
+        // 避免依赖 polars 内部的 `_materialize_struct_av` 私有 API。
+        value @ AnyValue::Struct(_, _, _) => match value.clone().into_static() {
+            AnyValue::StructOwned(payload) => {
+                let (values, fields) = payload.as_ref();
+                named_struct_literal(fields.iter().map(|f| f.name().as_str()), values)?
             }
-        };
-        Ok(formatted)
+            other => format_hive_literal(&other)?,
+        },
+        AnyValue::StructOwned(payload) => {
+            let (values, fields) = payload.as_ref();
+            named_struct_literal(fields.iter().map(|f| f.name().as_str()), values)?
+        }
+        AnyValue::String(s) => format!("'{}'", escape_hive_string(s)),
+        AnyValue::StringOwned(s) => format!("'{}'", escape_hive_string(s)),
+        other => format!("'{}'", escape_hive_string(&format!("{other}"))),
+    };
+    Ok(formatted)
+}
+
+/// 用字段名与字段值构造 `named_struct('f1', v1, 'f2', v2, ...)` 字面量
+fn named_struct_literal<'a>(
+    names: impl Iterator<Item = &'a str>,
+    values: &[AnyValue],
+) -> Result<String> {
+    let parts = names
+        .zip(values.iter())
+        .map(|(name, field_av)| {
+            let escaped = escape_hive_string(name);
+            let literal = format_hive_literal(field_av)?;
+            Ok(format!("'{escaped}', {literal}"))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    Ok(format!("named_struct({})", parts.join(", ")))
+}
+
+/// 将 i128 按给定标度渲染为十进制字符串 (无浮点误差)
+fn render_decimal(value: i128, scale: usize) -> String {
+    if scale == 0 {
+        return value.to_string();
     }
+    let negative = value < 0;
+    let digits = value.unsigned_abs().to_string();
+    let digits = if digits.len() <= scale {
+        format!("{}{}", "0".repeat(scale - digits.len() + 1), digits)
+    } else {
+        digits
+    };
+    let split = digits.len() - scale;
+    let (int_part, frac_part) = digits.split_at(split);
+    let sign = if negative { "-" } else { "" };
+    format!("{sign}{int_part}.{frac_part}")
+}
+
+/// 按时间单位将整数时间戳渲染为 `yyyy-MM-dd HH:mm:ss[.fff]` 形式
+fn render_timestamp(value: i64, unit: TimeUnit) -> Result<String> {
+    let (secs, nanos) = match unit {
+        TimeUnit::Nanoseconds => (value.div_euclid(1_000_000_000), value.rem_euclid(1_000_000_000)),
+        TimeUnit::Microseconds => (value.div_euclid(1_000_000), value.rem_euclid(1_000_000) * 1_000),
+        TimeUnit::Milliseconds => (value.div_euclid(1_000), value.rem_euclid(1_000) * 1_000_000),
+    };
+    let dt = chrono::DateTime::from_timestamp(secs, nanos as u32)
+        .ok_or_else(|| anyhow!("非法的时间戳值: {value}"))?
+        .naive_utc();
+    if nanos == 0 {
+        Ok(dt.format("%Y-%m-%d %H:%M:%S").to_string())
+    } else {
+        Ok(dt.format("%Y-%m-%d %H:%M:%S%.3f").to_string())
+    }
+}
+
+/// 将字节切片编码为小写十六进制字符串
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+/// 转义 Hive 单引号字符串中的特殊字符 (反斜杠、单引号、换行等)
+fn escape_hive_string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for ch in s.chars() {
+        match ch {
+            '\\' => out.push_str("\\\\"),
+            '\'' => out.push_str("\\'"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            other => out.push(other),
+        }
+    }
+    out
 }
 
 /// Hive写入上下文管理器
@@ -1001,6 +2258,24 @@ impl RustHiveWriteContext {
             .create_table_from_dataframe(df, table_name, partition_cols)
     }
 
+    /// CTAS：将查询结果物化为表
+    #[pyo3(signature = (table_name, sql, mode = None, stored_as = None))]
+    fn create_table_as_select(
+        &self,
+        table_name: String,
+        sql: String,
+        mode: Option<WriteMode>,
+        stored_as: Option<String>,
+    ) -> PyResult<()> {
+        self.writer
+            .create_table_as_select(table_name, sql, mode, stored_as)
+    }
+
+    /// 查询表的存储位置与列信息
+    fn get_table_location(&self, table_name: String) -> PyResult<(String, Vec<(String, String)>)> {
+        self.writer.get_table_location(table_name)
+    }
+
     /// 删除表
     fn drop_table(&self, table_name: String, if_exists: Option<bool>) -> PyResult<()> {
         self.writer.drop_table(table_name, if_exists)
@@ -1022,3 +2297,87 @@ impl RustHiveWriteContext {
 fn connect_hive_writer(config: Option<HiveConfig>) -> RustHiveWriteContext {
     RustHiveWriteContext::new(config)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn polars_type_mapping_covers_scalars_and_nested() {
+        let writer = RustHiveWriter::new(None);
+        assert_eq!(writer.polars_to_hive_type(&DataType::Int32).unwrap(), "INT");
+        assert_eq!(writer.polars_to_hive_type(&DataType::Int64).unwrap(), "BIGINT");
+        assert_eq!(writer.polars_to_hive_type(&DataType::Float32).unwrap(), "FLOAT");
+        assert_eq!(writer.polars_to_hive_type(&DataType::Float64).unwrap(), "DOUBLE");
+        assert_eq!(writer.polars_to_hive_type(&DataType::Boolean).unwrap(), "BOOLEAN");
+        assert_eq!(writer.polars_to_hive_type(&DataType::String).unwrap(), "STRING");
+        assert_eq!(writer.polars_to_hive_type(&DataType::Date).unwrap(), "DATE");
+        assert_eq!(
+            writer
+                .polars_to_hive_type(&DataType::Decimal(Some(12), Some(2)))
+                .unwrap(),
+            "DECIMAL(12,2)"
+        );
+        assert_eq!(
+            writer
+                .polars_to_hive_type(&DataType::List(Box::new(DataType::Int64)))
+                .unwrap(),
+            "ARRAY<BIGINT>"
+        );
+    }
+
+    #[test]
+    fn decimal_renders_without_float_artifacts() {
+        assert_eq!(render_decimal(12345, 2), "123.45");
+        assert_eq!(render_decimal(-12345, 2), "-123.45");
+        assert_eq!(render_decimal(5, 3), "0.005");
+        assert_eq!(render_decimal(42, 0), "42");
+    }
+
+    #[test]
+    fn timestamp_formats_by_time_unit() {
+        assert_eq!(
+            render_timestamp(0, TimeUnit::Milliseconds).unwrap(),
+            "1970-01-01 00:00:00"
+        );
+        assert_eq!(
+            render_timestamp(1_500, TimeUnit::Milliseconds).unwrap(),
+            "1970-01-01 00:00:01.500"
+        );
+    }
+
+    #[test]
+    fn string_escaping_handles_quotes_backslashes_and_newlines() {
+        assert_eq!(escape_hive_string("a'b"), "a\\'b");
+        assert_eq!(escape_hive_string("a\\b"), "a\\\\b");
+        assert_eq!(escape_hive_string("a\nb"), "a\\nb");
+    }
+
+    #[test]
+    fn progress_line_extracts_map_reduce_average() {
+        assert_eq!(
+            parse_progress_line("Stage-1 map = 40%, reduce = 60%"),
+            Some(50.0)
+        );
+        assert_eq!(parse_progress_line("<PERFLOG method=compile>"), None);
+    }
+
+    #[test]
+    fn partition_groups_split_by_key_and_drop_partition_columns() {
+        let df = df! {
+            "dt" => ["2025-01-01", "2025-01-01", "2025-01-02"],
+            "value" => [1i64, 2, 3],
+        }
+        .unwrap();
+
+        let mut groups = partition_groups(&df, &["dt".to_string()]).unwrap();
+        groups.sort_by(|a, b| a.0.cmp(&b.0));
+
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].0, "dt=2025-01-01");
+        assert_eq!(groups[0].1, "dt='2025-01-01'");
+        // 分区列不应保留在数据文件里
+        assert!(!groups[0].2.get_column_names().iter().any(|c| c.as_str() == "dt"));
+        assert_eq!(groups[0].2.height(), 2);
+    }
+}